@@ -0,0 +1,26 @@
+//! Small geo helpers shared between modules.
+
+/// Mean Earth radius in meters, used for haversine distance and projection calculations.
+pub(crate) const EARTH_RADIUS_M: f64 = 6_371_000.0;
+
+/// Great-circle distance between two coordinates in meters.
+pub(crate) fn haversine_distance_m(lat1: f64, lon1: f64, lat2: f64, lon2: f64) -> f64 {
+    let d_lat = (lat2 - lat1).to_radians();
+    let d_lon = (lon2 - lon1).to_radians();
+    let a = (d_lat / 2.0).sin().powi(2)
+        + lat1.to_radians().cos() * lat2.to_radians().cos() * (d_lon / 2.0).sin().powi(2);
+    2.0 * EARTH_RADIUS_M * a.sqrt().asin()
+}
+
+/// Projects decimal coordinates onto an equirectangular plane in meters, around
+/// `reference_lat`.
+///
+/// Distances measured in this plane approximate true (haversine) distances close to
+/// `reference_lat` and stay internally consistent everywhere else, which is what's needed for a
+/// Euclidean spatial index: the plane a query point is projected into must be the same plane
+/// indexed objects live in.
+pub(crate) fn equirectangular_project(lat: f64, lon: f64, reference_lat: f64) -> [f64; 2] {
+    let x = lon.to_radians() * reference_lat.to_radians().cos() * EARTH_RADIUS_M;
+    let y = lat.to_radians() * EARTH_RADIUS_M;
+    [x, y]
+}
@@ -0,0 +1,231 @@
+//! A routing graph built from nodes and edges, supporting shortest-path queries.
+
+use crate::geo::haversine_distance_m;
+use crate::osrm::{Edge, Node};
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+
+/// An adjacency graph stored as a compressed sparse row (CSR), weighted by haversine distance.
+pub struct Graph {
+    coordinates: Vec<(f64, f64)>,
+    /// `offsets[i] .. offsets[i + 1]` indexes into `targets` for the edges leaving node `i`.
+    offsets: Vec<u32>,
+    targets: Vec<u32>,
+}
+
+impl Graph {
+    /// Builds a graph from node and edge streams.
+    ///
+    /// Nodes must be given in the order that `edges` reference them by index.
+    pub fn new(nodes: impl IntoIterator<Item = Node>, edges: impl IntoIterator<Item = Edge>) -> Graph {
+        let coordinates: Vec<(f64, f64)> = nodes
+            .into_iter()
+            .map(|n| (n.latitude(), n.longitude()))
+            .collect();
+
+        let mut by_source: Vec<Vec<u32>> = vec![Vec::new(); coordinates.len()];
+        for edge in edges {
+            by_source[edge.source_node_index as usize].push(edge.target_node_index);
+        }
+
+        let mut offsets = Vec::with_capacity(by_source.len() + 1);
+        let mut targets = Vec::new();
+        offsets.push(0);
+        for adjacent in by_source {
+            targets.extend(adjacent);
+            offsets.push(targets.len() as u32);
+        }
+
+        Graph {
+            coordinates,
+            offsets,
+            targets,
+        }
+    }
+
+    /// Number of nodes in the graph.
+    pub fn node_count(&self) -> usize {
+        self.coordinates.len()
+    }
+
+    fn neighbors(&self, node_index: u32) -> &[u32] {
+        let start = self.offsets[node_index as usize] as usize;
+        let end = self.offsets[node_index as usize + 1] as usize;
+        &self.targets[start..end]
+    }
+
+    fn distance_m(&self, a: u32, b: u32) -> f64 {
+        let (lat1, lon1) = self.coordinates[a as usize];
+        let (lat2, lon2) = self.coordinates[b as usize];
+        haversine_distance_m(lat1, lon1, lat2, lon2)
+    }
+
+    /// Finds the shortest path from `source_index` to `target_index`, returning the sequence of
+    /// node indices along the path, or `None` if no path exists.
+    pub fn shortest_path(&self, source_index: u32, target_index: u32) -> Option<Vec<u32>> {
+        let mut dist = vec![f64::INFINITY; self.node_count()];
+        let mut prev = vec![u32::MAX; self.node_count()];
+        let mut heap = BinaryHeap::new();
+
+        dist[source_index as usize] = 0.0;
+        heap.push(HeapEntry { cost: 0.0, node_index: source_index });
+
+        while let Some(HeapEntry { cost, node_index }) = heap.pop() {
+            if node_index == target_index {
+                break;
+            }
+
+            if cost > dist[node_index as usize] {
+                continue;
+            }
+
+            for &neighbor in self.neighbors(node_index) {
+                let next_cost = cost + self.distance_m(node_index, neighbor);
+                if next_cost < dist[neighbor as usize] {
+                    dist[neighbor as usize] = next_cost;
+                    prev[neighbor as usize] = node_index;
+                    heap.push(HeapEntry { cost: next_cost, node_index: neighbor });
+                }
+            }
+        }
+
+        if dist[target_index as usize].is_infinite() {
+            return None;
+        }
+
+        let mut path = vec![target_index];
+        let mut current = target_index;
+        while current != source_index {
+            current = prev[current as usize];
+            path.push(current);
+        }
+        path.reverse();
+
+        Some(path)
+    }
+
+    /// Encodes a path of node indices as a Google-encoded polyline string.
+    ///
+    /// `precision` is the number of decimal digits to keep (usually 5 or 6).
+    pub fn to_polyline(&self, path: &[u32], precision: u32) -> String {
+        let scale = 10f64.powi(precision as i32);
+        let mut result = String::new();
+        let mut prev_lat = 0i64;
+        let mut prev_lon = 0i64;
+
+        for &node_index in path {
+            let (lat, lon) = self.coordinates[node_index as usize];
+            let lat = (lat * scale).round() as i64;
+            let lon = (lon * scale).round() as i64;
+
+            encode_value(lat - prev_lat, &mut result);
+            encode_value(lon - prev_lon, &mut result);
+
+            prev_lat = lat;
+            prev_lon = lon;
+        }
+
+        result
+    }
+}
+
+/// Entry in the Dijkstra priority queue, ordered by ascending cost (`BinaryHeap` is a max-heap).
+struct HeapEntry {
+    cost: f64,
+    node_index: u32,
+}
+
+impl PartialEq for HeapEntry {
+    fn eq(&self, other: &Self) -> bool {
+        self.cost == other.cost
+    }
+}
+
+impl Eq for HeapEntry {}
+
+impl PartialOrd for HeapEntry {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for HeapEntry {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.cost.partial_cmp(&self.cost).unwrap_or(Ordering::Equal)
+    }
+}
+
+/// Zig-zag encodes and appends a single coordinate delta to a polyline string.
+fn encode_value(value: i64, out: &mut String) {
+    let mut value = (value << 1) ^ (value >> 63);
+
+    loop {
+        let mut chunk = (value & 0x1f) as u8;
+        value >>= 5;
+
+        if value != 0 {
+            chunk |= 0x20;
+        }
+
+        out.push((chunk + 63) as char);
+
+        if value == 0 {
+            break;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn node(node_id: i64, lat: f64, lon: f64) -> Node {
+        Node {
+            raw_longitude: (lon * 1_000_000.0).round() as i32,
+            raw_latitude: (lat * 1_000_000.0).round() as i32,
+            node_id,
+        }
+    }
+
+    #[test]
+    fn to_polyline_matches_the_canonical_google_example() {
+        let nodes = vec![
+            node(1, 38.5, -120.2),
+            node(2, 40.7, -120.95),
+            node(3, 43.252, -126.453),
+        ];
+        let graph = Graph::new(nodes, Vec::new());
+
+        let encoded = graph.to_polyline(&[0, 1, 2], 5);
+
+        assert_eq!(encoded, "_p~iF~ps|U_ulLnnqC_mqNvxq`@");
+    }
+
+    #[test]
+    fn shortest_path_picks_the_cheaper_route() {
+        // 0 -> 1 -> 2 is a long way around; 0 -> 2 direct is shorter.
+        let nodes = vec![
+            node(1, 0.0, 0.0),
+            node(2, 1.0, 1.0),
+            node(3, 0.01, 0.01),
+        ];
+        let edges = vec![
+            Edge { source_node_index: 0, target_node_index: 1 },
+            Edge { source_node_index: 1, target_node_index: 2 },
+            Edge { source_node_index: 0, target_node_index: 2 },
+        ];
+        let graph = Graph::new(nodes, edges);
+
+        let path = graph.shortest_path(0, 2).unwrap();
+
+        assert_eq!(path, vec![0, 2]);
+    }
+
+    #[test]
+    fn shortest_path_returns_none_when_unreachable() {
+        let nodes = vec![node(1, 0.0, 0.0), node(2, 1.0, 1.0)];
+        let graph = Graph::new(nodes, Vec::new());
+
+        assert_eq!(graph.shortest_path(0, 1), None);
+    }
+}
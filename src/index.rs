@@ -0,0 +1,146 @@
+//! An in-memory spatial index over `Node`s, built on an R-tree.
+
+use crate::geo::equirectangular_project;
+use crate::osrm::Node;
+use rstar::{PointDistance, RTree, RTreeObject, AABB};
+
+/// A node as stored in the R-tree: its sequential index alongside its position on the index's
+/// equirectangular projection plane, in meters.
+#[derive(Clone, Debug)]
+struct IndexedNode {
+    node_index: u32,
+    projected: [f64; 2],
+}
+
+impl RTreeObject for IndexedNode {
+    type Envelope = AABB<[f64; 2]>;
+
+    fn envelope(&self) -> Self::Envelope {
+        AABB::from_point(self.projected)
+    }
+}
+
+impl PointDistance for IndexedNode {
+    fn distance_2(&self, point: &[f64; 2]) -> f64 {
+        // Squared Euclidean distance on the same projection plane as `envelope`, so tree pruning
+        // (which compares envelope distances) and leaf comparisons (this method) share one unit:
+        // square meters.
+        let dx = self.projected[0] - point[0];
+        let dy = self.projected[1] - point[1];
+        dx * dx + dy * dy
+    }
+}
+
+/// An in-memory R-tree index over `Node`s, supporting nearest-node and radius queries.
+pub struct NodeIndex {
+    tree: RTree<IndexedNode>,
+    nodes: Vec<Node>,
+    /// Latitude the equirectangular projection is centered on, chosen as the mean latitude of
+    /// the indexed nodes to keep projection distortion low across queries.
+    reference_lat: f64,
+}
+
+impl NodeIndex {
+    /// Builds an index from an iterator of nodes.
+    ///
+    /// Nodes are assigned sequential indices in iteration order, matching how `Edge`s reference
+    /// nodes by index.
+    pub fn new(nodes: impl IntoIterator<Item = Node>) -> NodeIndex {
+        let nodes: Vec<Node> = nodes.into_iter().collect();
+
+        let reference_lat = if nodes.is_empty() {
+            0.0
+        } else {
+            nodes.iter().map(Node::latitude).sum::<f64>() / nodes.len() as f64
+        };
+
+        let objects = nodes
+            .iter()
+            .enumerate()
+            .map(|(i, n)| IndexedNode {
+                node_index: i as u32,
+                projected: equirectangular_project(n.latitude(), n.longitude(), reference_lat),
+            })
+            .collect();
+
+        NodeIndex {
+            tree: RTree::bulk_load(objects),
+            nodes,
+            reference_lat,
+        }
+    }
+
+    fn project(&self, lat: f64, lon: f64) -> [f64; 2] {
+        equirectangular_project(lat, lon, self.reference_lat)
+    }
+
+    /// Returns the node closest to the given coordinates, along with its index.
+    pub fn nearest(&self, lat: f64, lon: f64) -> Option<(u32, &Node)> {
+        self.tree
+            .nearest_neighbor(&self.project(lat, lon))
+            .map(|indexed| (indexed.node_index, &self.nodes[indexed.node_index as usize]))
+    }
+
+    /// Returns up to `k` nodes closest to the given coordinates, nearest first.
+    pub fn k_nearest(&self, lat: f64, lon: f64, k: usize) -> Vec<(u32, &Node)> {
+        self.tree
+            .nearest_neighbor_iter(&self.project(lat, lon))
+            .take(k)
+            .map(|indexed| (indexed.node_index, &self.nodes[indexed.node_index as usize]))
+            .collect()
+    }
+
+    /// Returns all nodes within `meters` of the given coordinates.
+    pub fn within_radius(&self, lat: f64, lon: f64, meters: f64) -> Vec<(u32, &Node)> {
+        self.tree
+            .locate_within_distance(self.project(lat, lon), meters * meters)
+            .map(|indexed| (indexed.node_index, &self.nodes[indexed.node_index as usize]))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn node(node_id: i64, lat: f64, lon: f64) -> Node {
+        Node {
+            raw_longitude: (lon * 1_000_000.0) as i32,
+            raw_latitude: (lat * 1_000_000.0) as i32,
+            node_id,
+        }
+    }
+
+    #[test]
+    fn nearest_is_correct_at_high_latitude() {
+        // At latitude 60°, a degree of longitude covers about half the ground distance of a
+        // degree of latitude; raw-coordinate (unprojected) distance comparisons get this wrong.
+        let index = NodeIndex::new(vec![
+            node(1, 60.0, 0.18),
+            node(2, 60.1, 0.0),
+            node(3, 60.0, -0.5),
+        ]);
+
+        // 0.18 degrees of longitude at 60N (about 10 km) is closer in meters than 0.1 degrees of
+        // latitude (about 11 km), although it is the larger difference in raw degrees.
+        let (node_index, found) = index.nearest(60.0, 0.0).unwrap();
+        assert_eq!(node_index, 0);
+        assert_eq!(found.node_id, 1);
+    }
+
+    #[test]
+    fn within_radius_finds_a_nearby_node() {
+        let index = NodeIndex::new(vec![
+            node(1, 52.0, 13.0),
+            node(2, 52.004, 13.0), // roughly 445 m north
+            node(3, 53.0, 14.0),   // far away
+        ]);
+
+        let found = index.within_radius(52.0, 13.0, 500.0);
+        let found_ids: Vec<i64> = found.iter().map(|(_, n)| n.node_id).collect();
+
+        assert!(found_ids.contains(&1));
+        assert!(found_ids.contains(&2));
+        assert!(!found_ids.contains(&3));
+    }
+}
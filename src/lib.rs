@@ -49,5 +49,10 @@ fn main() -> Result<(), std::io::Error> {
 */
 
 pub use osrm::*;
+pub use index::*;
+pub use graph::*;
 
 pub mod osrm;
+pub mod index;
+pub mod graph;
+mod geo;
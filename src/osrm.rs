@@ -1,7 +1,10 @@
-/// Read entries from an *.osrm file.
+//! Read and write entries of an *.osrm file.
 
+use crate::geo::{haversine_distance_m, EARTH_RADIUS_M};
 use byteorder::{ByteOrder, LittleEndian};
-use std::io::Read;
+use rayon::prelude::*;
+use std::collections::HashSet;
+use std::io::{Read, Write};
 use tar;
 
 
@@ -11,6 +14,15 @@ const NODE_SIZE: usize = 16;
 /// Size of an edge in bytes.
 const EDGE_SIZE: usize = 32;
 
+/// Size of a barrier node entry in bytes.
+const BARRIER_SIZE: usize = 4;
+
+/// Size of a traffic light node entry in bytes.
+const TRAFFIC_LIGHT_SIZE: usize = 4;
+
+/// Size of a turn restriction in bytes.
+const RESTRICTION_SIZE: usize = 12;
+
 
 /// A reader for *.osrm files that allows iterating over it's entries.
 pub struct OsrmReader<R: Read> {
@@ -41,9 +53,20 @@ pub struct OsrmEntries<'a, R: 'a + Read> {
     entries: tar::Entries<'a, R>,
 }
 
+/// The tar paths and binary layouts `nbg_nodes`, `barriers`, `traffic_lights` and `restrictions`
+/// map to below (and the `BARRIER_SIZE`/`TRAFFIC_LIGHT_SIZE`/`RESTRICTION_SIZE` constants they use)
+/// are this crate's own convention, modeled on the already-established `nodes`/`edges` layout —
+/// they have not been checked against a real `osrm-extract` output archive or an official OSRM
+/// format reference. Verify them against an actual extract before relying on this crate to read
+/// real OSRM data for these sections.
 pub enum Entry<'a, R: Read> {
     Nodes(OsrmNodes<'a, R>),
     Edges(OsrmEdges<'a, R>),
+    /// Coordinates of the nodes of the node-based graph, keyed by their OSM node ID.
+    NodeCoordinates(OsrmNodes<'a, R>),
+    Barriers(OsrmBarriers<'a, R>),
+    TrafficLights(OsrmTrafficLights<'a, R>),
+    Restrictions(OsrmRestrictions<'a, R>),
     Unknown(tar::Entry<'a, R>),
 }
 
@@ -59,6 +82,10 @@ impl<'a, R: 'a + Read> Iterator for OsrmEntries<'a, R> {
                     match path {
                         Some("/extractor/nodes") => Ok(Entry::Nodes(OsrmNodes::new(entry)?)),
                         Some("/extractor/edges") => Ok(Entry::Edges(OsrmEdges::new(entry)?)),
+                        Some("/extractor/nbg_nodes") => Ok(Entry::NodeCoordinates(OsrmNodes::new(entry)?)),
+                        Some("/extractor/barriers") => Ok(Entry::Barriers(OsrmBarriers::new(entry)?)),
+                        Some("/extractor/traffic_lights") => Ok(Entry::TrafficLights(OsrmTrafficLights::new(entry)?)),
+                        Some("/extractor/restrictions") => Ok(Entry::Restrictions(OsrmRestrictions::new(entry)?)),
                         Some(_) => Ok(Entry::Unknown(entry)),
                         None => Ok(Entry::Unknown(entry)),
                     }
@@ -97,6 +124,11 @@ impl Node {
     pub fn latitude(&self) -> f64 {
         self.raw_latitude as f64 * 0.000001
     }
+
+    /// Returns the great-circle distance to the given coordinates in meters.
+    pub fn haversine_distance_m(&self, lat: f64, lon: f64) -> f64 {
+        haversine_distance_m(self.latitude(), self.longitude(), lat, lon)
+    }
 }
 
 impl<'a, R: 'a + Read> OsrmNodes<'a, R> {
@@ -135,15 +167,213 @@ impl<'a, R: 'a + Read> Iterator for OsrmNodes<'a, R> {
         }
         self.current_node_index += 1;
 
-        let raw_longitude = LittleEndian::read_i32(&buf[0..4]);
-        let raw_latitude = LittleEndian::read_i32(&buf[4..8]);
-        let node_id = LittleEndian::read_i64(&buf[8..16]);
+        Some(Ok(decode_node(&buf)))
+    }
+}
 
-        Some(Ok(Node{
-            raw_longitude,
-            raw_latitude,
-            node_id,
-        }))
+impl<'a, R: 'a + Read> OsrmNodes<'a, R> {
+    /// Reads all remaining nodes, decoding them in parallel across multiple threads.
+    ///
+    /// This first drains the remaining entry data into a single buffer, then hands chunks of it
+    /// to rayon's thread pool for decoding. For large extracts this is much faster than decoding
+    /// one record at a time through the shared `tar::Entry` reader.
+    pub fn read_all_nodes_parallel(mut self) -> Result<Vec<Node>, std::io::Error> {
+        let data = self.read_remaining_bytes()?;
+        Ok(data.par_chunks(NODE_SIZE).map(decode_node).collect())
+    }
+
+    /// Reads all remaining nodes in parallel, folding them with `map` and `reduce` without
+    /// materializing the full `Vec<Node>`.
+    ///
+    /// `identity` produces the starting value for each parallel fold, `map` converts a decoded
+    /// node into the accumulator type, and `reduce` combines two partial accumulators.
+    pub fn par_map_reduce<T, ID, Map, Reduce>(
+        mut self,
+        identity: ID,
+        map: Map,
+        reduce: Reduce,
+    ) -> Result<T, std::io::Error>
+    where
+        T: Send,
+        ID: Fn() -> T + Sync + Send,
+        Map: Fn(Node) -> T + Sync + Send,
+        Reduce: Fn(T, T) -> T + Sync + Send,
+    {
+        let data = self.read_remaining_bytes()?;
+
+        Ok(data
+            .par_chunks(NODE_SIZE)
+            .map(|chunk| map(decode_node(chunk)))
+            .reduce(&identity, &reduce))
+    }
+
+    /// Reads the remaining, not yet decoded node data into a single buffer.
+    fn read_remaining_bytes(&mut self) -> Result<Vec<u8>, std::io::Error> {
+        let remaining_nodes = self.number_of_nodes - self.current_node_index;
+        let mut data = vec![0u8; remaining_nodes as usize * NODE_SIZE];
+        self.entry.read_exact(&mut data)?;
+        self.current_node_index = self.number_of_nodes;
+
+        Ok(data)
+    }
+}
+
+/// Decodes a single node from a `NODE_SIZE`-byte buffer.
+fn decode_node(buf: &[u8]) -> Node {
+    let raw_longitude = LittleEndian::read_i32(&buf[0..4]);
+    let raw_latitude = LittleEndian::read_i32(&buf[4..8]);
+    let node_id = LittleEndian::read_i64(&buf[8..16]);
+
+    Node {
+        raw_longitude,
+        raw_latitude,
+        node_id,
+    }
+}
+
+impl<'a, R: 'a + Read> OsrmNodes<'a, R> {
+    /// Streams only nodes whose raw coordinates fall inside the given bounding box.
+    ///
+    /// Comparisons stay in raw `*1e6` space to avoid a float conversion per record.
+    pub fn within_bbox(self, min_lat: f64, min_lon: f64, max_lat: f64, max_lon: f64) -> WithinBbox<'a, R> {
+        WithinBbox {
+            node_index: self.current_node_index as u32,
+            nodes: self,
+            min_raw_latitude: (min_lat * 1_000_000.0) as i32,
+            min_raw_longitude: (min_lon * 1_000_000.0) as i32,
+            max_raw_latitude: (max_lat * 1_000_000.0) as i32,
+            max_raw_longitude: (max_lon * 1_000_000.0) as i32,
+        }
+    }
+
+    /// Streams only nodes within `meters` of the given center coordinates.
+    ///
+    /// A cheap bounding-box pre-check in raw coordinate space rules out most records; haversine
+    /// distance is only computed for nodes that pass it.
+    pub fn within_distance(self, center_lat: f64, center_lon: f64, meters: f64) -> WithinDistance<'a, R> {
+        // The latitude extent and the widest longitude extent of a circle of `meters` on the same
+        // sphere `haversine_distance_m` uses give a conservative raw-space bounding box around the
+        // center point. The box does not wrap around the antimeridian.
+        let angle = meters / EARTH_RADIUS_M;
+        let lat_margin = angle.to_degrees();
+        let sin_ratio = angle.sin() / center_lat.to_radians().cos();
+        let lon_margin = if angle >= std::f64::consts::FRAC_PI_2 || sin_ratio >= 1.0 {
+            180.0
+        } else {
+            sin_ratio.asin().to_degrees()
+        };
+
+        WithinDistance {
+            bbox: self.within_bbox(
+                center_lat - lat_margin,
+                center_lon - lon_margin,
+                center_lat + lat_margin,
+                center_lon + lon_margin,
+            ),
+            center_lat,
+            center_lon,
+            meters,
+        }
+    }
+}
+
+/// A filtered subset of node indices, derived from a node query such as `within_bbox`.
+///
+/// Use `allows` (or `OsrmEdges::filtered`) to keep only edges whose endpoints lie in the region
+/// the filter was built from, without allocating the full graph.
+pub struct NodeFilter {
+    allowed: HashSet<u32>,
+}
+
+impl NodeFilter {
+    /// Returns whether the given node index is part of the filter.
+    pub fn allows(&self, node_index: u32) -> bool {
+        self.allowed.contains(&node_index)
+    }
+}
+
+/// An iterator over nodes within a bounding box. See `OsrmNodes::within_bbox`.
+pub struct WithinBbox<'a, R: Read> {
+    nodes: OsrmNodes<'a, R>,
+    node_index: u32,
+    min_raw_latitude: i32,
+    min_raw_longitude: i32,
+    max_raw_latitude: i32,
+    max_raw_longitude: i32,
+}
+
+impl<'a, R: 'a + Read> WithinBbox<'a, R> {
+    fn matches(&self, node: &Node) -> bool {
+        node.raw_latitude >= self.min_raw_latitude
+            && node.raw_latitude <= self.max_raw_latitude
+            && node.raw_longitude >= self.min_raw_longitude
+            && node.raw_longitude <= self.max_raw_longitude
+    }
+
+    /// Consumes the remaining filtered nodes into a `NodeFilter` of their indices.
+    pub fn node_filter(self) -> Result<NodeFilter, std::io::Error> {
+        let mut allowed = HashSet::new();
+        for item in self {
+            let (node_index, _) = item?;
+            allowed.insert(node_index);
+        }
+        Ok(NodeFilter { allowed })
+    }
+}
+
+impl<'a, R: 'a + Read> Iterator for WithinBbox<'a, R> {
+    type Item = Result<(u32, Node), std::io::Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let node = match self.nodes.next()? {
+                Ok(node) => node,
+                Err(err) => return Some(Err(err)),
+            };
+            let node_index = self.node_index;
+            self.node_index += 1;
+
+            if self.matches(&node) {
+                return Some(Ok((node_index, node)));
+            }
+        }
+    }
+}
+
+/// An iterator over nodes within a radius of a center point. See `OsrmNodes::within_distance`.
+pub struct WithinDistance<'a, R: Read> {
+    bbox: WithinBbox<'a, R>,
+    center_lat: f64,
+    center_lon: f64,
+    meters: f64,
+}
+
+impl<'a, R: 'a + Read> WithinDistance<'a, R> {
+    /// Consumes the remaining filtered nodes into a `NodeFilter` of their indices.
+    pub fn node_filter(self) -> Result<NodeFilter, std::io::Error> {
+        let mut allowed = HashSet::new();
+        for item in self {
+            let (node_index, _) = item?;
+            allowed.insert(node_index);
+        }
+        Ok(NodeFilter { allowed })
+    }
+}
+
+impl<'a, R: 'a + Read> Iterator for WithinDistance<'a, R> {
+    type Item = Result<(u32, Node), std::io::Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let (node_index, node) = match self.bbox.next()? {
+                Ok(item) => item,
+                Err(err) => return Some(Err(err)),
+            };
+
+            if node.haversine_distance_m(self.center_lat, self.center_lon) <= self.meters {
+                return Some(Ok((node_index, node)));
+            }
+        }
     }
 }
 
@@ -202,12 +432,569 @@ impl<'a, R: 'a + Read> Iterator for OsrmEdges<'a, R> {
         }
         self.current_edge_index += 1;
 
-        let source_node_index = LittleEndian::read_u32(&buf[0..4]);
-        let target_node_index = LittleEndian::read_u32(&buf[4..8]);
+        Some(Ok(decode_edge(&buf)))
+    }
+}
+
+impl<'a, R: 'a + Read> OsrmEdges<'a, R> {
+    /// Reads all remaining edges, decoding them in parallel across multiple threads.
+    ///
+    /// This first drains the remaining entry data into a single buffer, then hands chunks of it
+    /// to rayon's thread pool for decoding. For large extracts this is much faster than decoding
+    /// one record at a time through the shared `tar::Entry` reader.
+    pub fn read_all_edges_parallel(mut self) -> Result<Vec<Edge>, std::io::Error> {
+        let data = self.read_remaining_bytes()?;
+        Ok(data.par_chunks(EDGE_SIZE).map(decode_edge).collect())
+    }
+
+    /// Reads all remaining edges in parallel, folding them with `map` and `reduce` without
+    /// materializing the full `Vec<Edge>`.
+    ///
+    /// `identity` produces the starting value for each parallel fold, `map` converts a decoded
+    /// edge into the accumulator type, and `reduce` combines two partial accumulators.
+    pub fn par_map_reduce<T, ID, Map, Reduce>(
+        mut self,
+        identity: ID,
+        map: Map,
+        reduce: Reduce,
+    ) -> Result<T, std::io::Error>
+    where
+        T: Send,
+        ID: Fn() -> T + Sync + Send,
+        Map: Fn(Edge) -> T + Sync + Send,
+        Reduce: Fn(T, T) -> T + Sync + Send,
+    {
+        let data = self.read_remaining_bytes()?;
+
+        Ok(data
+            .par_chunks(EDGE_SIZE)
+            .map(|chunk| map(decode_edge(chunk)))
+            .reduce(&identity, &reduce))
+    }
+
+    /// Reads the remaining, not yet decoded edge data into a single buffer.
+    fn read_remaining_bytes(&mut self) -> Result<Vec<u8>, std::io::Error> {
+        let remaining_edges = self.number_of_edges - self.current_edge_index;
+        let mut data = vec![0u8; remaining_edges as usize * EDGE_SIZE];
+        self.entry.read_exact(&mut data)?;
+        self.current_edge_index = self.number_of_edges;
+
+        Ok(data)
+    }
+}
+
+/// Decodes a single edge from an `EDGE_SIZE`-byte buffer.
+fn decode_edge(buf: &[u8]) -> Edge {
+    let source_node_index = LittleEndian::read_u32(&buf[0..4]);
+    let target_node_index = LittleEndian::read_u32(&buf[4..8]);
+
+    Edge {
+        source_node_index,
+        target_node_index,
+    }
+}
+
+impl<'a, R: 'a + Read> OsrmEdges<'a, R> {
+    /// Streams only edges whose source and target are both allowed by `filter`.
+    ///
+    /// Combine with `NodeFilter` from `OsrmNodes::within_bbox`/`within_distance` to extract a
+    /// city-sized subgraph from a country-sized `*.osrm` file in one pass.
+    pub fn filtered(self, filter: NodeFilter) -> FilteredEdges<'a, R> {
+        FilteredEdges {
+            edges: self,
+            filter,
+        }
+    }
+}
+
+/// An iterator over edges restricted to a `NodeFilter`. See `OsrmEdges::filtered`.
+pub struct FilteredEdges<'a, R: Read> {
+    edges: OsrmEdges<'a, R>,
+    filter: NodeFilter,
+}
+
+impl<'a, R: 'a + Read> Iterator for FilteredEdges<'a, R> {
+    type Item = Result<Edge, std::io::Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let edge = match self.edges.next()? {
+                Ok(edge) => edge,
+                Err(err) => return Some(Err(err)),
+            };
+
+            if self.filter.allows(edge.source_node_index) && self.filter.allows(edge.target_node_index) {
+                return Some(Ok(edge));
+            }
+        }
+    }
+}
+
+/// Types that can be serialized back into the exact little-endian layout used by *.osrm files.
+///
+/// This is the symmetric counterpart to the decoding done by `OsrmNodes` and `OsrmEdges`.
+pub trait ToWriter {
+    /// Size of the binary representation in bytes.
+    const SIZE: usize;
+
+    /// Writes the binary representation of `self` into `buf`, which must be at least `SIZE` bytes
+    /// long.
+    fn to_writer(&self, buf: &mut [u8]);
+}
+
+impl ToWriter for Node {
+    const SIZE: usize = NODE_SIZE;
+
+    fn to_writer(&self, buf: &mut [u8]) {
+        LittleEndian::write_i32(&mut buf[0..4], self.raw_longitude);
+        LittleEndian::write_i32(&mut buf[4..8], self.raw_latitude);
+        LittleEndian::write_i64(&mut buf[8..16], self.node_id);
+    }
+}
+
+impl ToWriter for Edge {
+    const SIZE: usize = EDGE_SIZE;
+
+    fn to_writer(&self, buf: &mut [u8]) {
+        // Only the fields modeled by `Edge` are known, the rest of the 32-byte record (weight,
+        // duration and flags used by osrm-routed) is zero-filled.
+        LittleEndian::write_u32(&mut buf[0..4], self.source_node_index);
+        LittleEndian::write_u32(&mut buf[4..8], self.target_node_index);
+        for byte in &mut buf[8..EDGE_SIZE] {
+            *byte = 0;
+        }
+    }
+}
+
+/// A writer for nodes and edges, producing an archive that `OsrmReader` can read back.
+///
+/// This crate does not implement OSRM's real on-disk format — in particular, the `FingerPrint`
+/// record `osrm-routed` requires as its leading entry, and the weight/duration/flags fields of the
+/// real edge record (`ToWriter for Edge` only writes the two node indices this crate models). An
+/// archive produced by `OsrmWriter` round-trips through `OsrmReader`; it does not load in
+/// `osrm-routed`. Writing a real fingerprint without a verified reference for its exact binary
+/// layout would just be a second fabricated format, so this writer makes no attempt at one —
+/// scope here is round-tripping within this crate, not `osrm-routed` interop.
+pub struct OsrmWriter<W: Write> {
+    builder: tar::Builder<W>,
+}
+
+impl<W: Write> OsrmWriter<W> {
+    /// Creates a new `OsrmWriter`.
+    pub fn new(writer: W) -> Result<OsrmWriter<W>, std::io::Error> {
+        let mut builder = tar::Builder::new(writer);
+        // `OsrmReader` matches entries by absolute path (e.g. "/extractor/nodes"), so the archive
+        // has to allow them.
+        builder.preserve_absolute(true);
+
+        Ok(OsrmWriter {
+            builder,
+        })
+    }
+
+    /// Writes nodes to the `/extractor/nodes` entry.
+    pub fn write_nodes<I: IntoIterator<Item = Node>>(&mut self, nodes: I) -> Result<(), std::io::Error> {
+        self.write_entry("/extractor/nodes", nodes)
+    }
+
+    /// Writes edges to the `/extractor/edges` entry.
+    pub fn write_edges<I: IntoIterator<Item = Edge>>(&mut self, edges: I) -> Result<(), std::io::Error> {
+        self.write_entry("/extractor/edges", edges)
+    }
+
+    fn write_entry<T: ToWriter, I: IntoIterator<Item = T>>(
+        &mut self,
+        path: &str,
+        items: I,
+    ) -> Result<(), std::io::Error> {
+        let mut data = Vec::new();
+        let mut buf = vec![0u8; T::SIZE];
+
+        for item in items {
+            item.to_writer(&mut buf);
+            data.extend_from_slice(&buf);
+        }
+
+        let mut header = tar::Header::new_gnu();
+        header.set_size(data.len() as u64);
+        header.set_mode(0o644);
+        header.set_cksum();
+
+        self.builder.append_data(&mut header, path, data.as_slice())
+    }
+
+    /// Finishes writing the archive, flushing any buffered data to the underlying writer.
+    pub fn finish(mut self) -> Result<(), std::io::Error> {
+        self.builder.finish()
+    }
+}
+
+/// An iterator over barrier nodes, given as indices into the node-based graph.
+///
+/// See `Entry` for a caveat about this section's assumed layout.
+pub struct OsrmBarriers<'a, R: Read> {
+    entry: tar::Entry<'a, R>,
+    pub number_of_barriers: u64,
+    current_barrier_index: u64,
+}
+
+impl<'a, R: 'a + Read> OsrmBarriers<'a, R> {
+    fn new(entry: tar::Entry<'a, R>) -> Result<OsrmBarriers<'a, R>, std::io::Error> {
+        let size = entry.header().size()?;
+        let number_of_barriers = size / BARRIER_SIZE as u64;
 
-        Some(Ok(Edge{
-            source_node_index,
-            target_node_index,
+        if size % BARRIER_SIZE as u64 != 0 {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "Barrier entry size is not divisible by the size of a barrier.")
+            );
+        }
+
+        Ok(OsrmBarriers {
+            entry,
+            number_of_barriers,
+            current_barrier_index: 0,
+        })
+    }
+}
+
+impl<'a, R: 'a + Read> Iterator for OsrmBarriers<'a, R> {
+    type Item = Result<u32, std::io::Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut buf = [0u8; BARRIER_SIZE];
+
+        if self.current_barrier_index >= self.number_of_barriers {
+            // Already read last barrier
+            return None;
+        }
+
+        if let Err(err) = self.entry.read_exact(&mut buf) {
+            return Some(Err(err));
+        }
+        self.current_barrier_index += 1;
+
+        Some(Ok(LittleEndian::read_u32(&buf)))
+    }
+}
+
+/// An iterator over traffic-light nodes, given as indices into the node-based graph.
+///
+/// See `Entry` for a caveat about this section's assumed layout.
+pub struct OsrmTrafficLights<'a, R: Read> {
+    entry: tar::Entry<'a, R>,
+    pub number_of_traffic_lights: u64,
+    current_traffic_light_index: u64,
+}
+
+impl<'a, R: 'a + Read> OsrmTrafficLights<'a, R> {
+    fn new(entry: tar::Entry<'a, R>) -> Result<OsrmTrafficLights<'a, R>, std::io::Error> {
+        let size = entry.header().size()?;
+        let number_of_traffic_lights = size / TRAFFIC_LIGHT_SIZE as u64;
+
+        if size % TRAFFIC_LIGHT_SIZE as u64 != 0 {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "Traffic light entry size is not divisible by the size of a traffic light.")
+            );
+        }
+
+        Ok(OsrmTrafficLights {
+            entry,
+            number_of_traffic_lights,
+            current_traffic_light_index: 0,
+        })
+    }
+}
+
+impl<'a, R: 'a + Read> Iterator for OsrmTrafficLights<'a, R> {
+    type Item = Result<u32, std::io::Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut buf = [0u8; TRAFFIC_LIGHT_SIZE];
+
+        if self.current_traffic_light_index >= self.number_of_traffic_lights {
+            // Already read last traffic light
+            return None;
+        }
+
+        if let Err(err) = self.entry.read_exact(&mut buf) {
+            return Some(Err(err));
+        }
+        self.current_traffic_light_index += 1;
+
+        Some(Ok(LittleEndian::read_u32(&buf)))
+    }
+}
+
+/// A turn restriction between two edges of the node-based graph, connected via a node.
+#[derive(Clone, Debug)]
+pub struct Restriction {
+    /// Index of the edge the restriction applies when coming from.
+    pub from_edge_index: u32,
+    /// Index of the node the restriction applies at.
+    pub via_node_index: u32,
+    /// Index of the edge the restriction applies when going to.
+    pub to_edge_index: u32,
+}
+
+/// An iterator over turn restrictions.
+///
+/// See `Entry` for a caveat about this section's assumed layout.
+pub struct OsrmRestrictions<'a, R: Read> {
+    entry: tar::Entry<'a, R>,
+    pub number_of_restrictions: u64,
+    current_restriction_index: u64,
+}
+
+impl<'a, R: 'a + Read> OsrmRestrictions<'a, R> {
+    fn new(entry: tar::Entry<'a, R>) -> Result<OsrmRestrictions<'a, R>, std::io::Error> {
+        let size = entry.header().size()?;
+        let number_of_restrictions = size / RESTRICTION_SIZE as u64;
+
+        if size % RESTRICTION_SIZE as u64 != 0 {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "Restriction entry size is not divisible by the size of a restriction.")
+            );
+        }
+
+        Ok(OsrmRestrictions {
+            entry,
+            number_of_restrictions,
+            current_restriction_index: 0,
+        })
+    }
+}
+
+impl<'a, R: 'a + Read> Iterator for OsrmRestrictions<'a, R> {
+    type Item = Result<Restriction, std::io::Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut buf = [0u8; RESTRICTION_SIZE];
+
+        if self.current_restriction_index >= self.number_of_restrictions {
+            // Already read last restriction
+            return None;
+        }
+
+        if let Err(err) = self.entry.read_exact(&mut buf) {
+            return Some(Err(err));
+        }
+        self.current_restriction_index += 1;
+
+        let from_edge_index = LittleEndian::read_u32(&buf[0..4]);
+        let via_node_index = LittleEndian::read_u32(&buf[4..8]);
+        let to_edge_index = LittleEndian::read_u32(&buf[8..12]);
+
+        Some(Ok(Restriction {
+            from_edge_index,
+            via_node_index,
+            to_edge_index,
         }))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    /// Builds an in-memory tar archive from raw entries, for exercising `OsrmReader` without
+    /// touching the filesystem.
+    fn build_archive(entries: &[(&str, &[u8])]) -> Vec<u8> {
+        let mut builder = tar::Builder::new(Vec::new());
+        builder.preserve_absolute(true);
+
+        for (path, data) in entries {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(data.len() as u64);
+            header.set_mode(0o644);
+            header.set_cksum();
+            builder.append_data(&mut header, path, *data).unwrap();
+        }
+
+        builder.into_inner().unwrap()
+    }
+
+    fn node(node_id: i64, raw_latitude: i32, raw_longitude: i32) -> Node {
+        Node {
+            raw_longitude,
+            raw_latitude,
+            node_id,
+        }
+    }
+
+    #[test]
+    fn writer_round_trips_nodes_and_edges() {
+        let nodes = vec![
+            node(1, 52_000_000, 13_000_000),
+            node(2, 53_000_000, 14_000_000),
+        ];
+        let edges = vec![Edge { source_node_index: 0, target_node_index: 1 }];
+
+        let mut buf = Vec::new();
+        {
+            let mut writer = OsrmWriter::new(&mut buf).unwrap();
+            writer.write_nodes(nodes.clone()).unwrap();
+            writer.write_edges(edges.clone()).unwrap();
+            writer.finish().unwrap();
+        }
+
+        let mut reader = OsrmReader::new(Cursor::new(buf));
+        let mut read_nodes = Vec::new();
+        let mut read_edges = Vec::new();
+
+        for entry in reader.entries().unwrap() {
+            match entry.unwrap() {
+                Entry::Nodes(it) => {
+                    for n in it {
+                        read_nodes.push(n.unwrap());
+                    }
+                },
+                Entry::Edges(it) => {
+                    for e in it {
+                        read_edges.push(e.unwrap());
+                    }
+                },
+                _ => {},
+            }
+        }
+
+        assert_eq!(read_nodes.len(), 2);
+        assert_eq!(read_nodes[0].node_id, 1);
+        assert_eq!(read_nodes[0].raw_latitude, 52_000_000);
+        assert_eq!(read_nodes[1].node_id, 2);
+        assert_eq!(read_edges.len(), 1);
+        assert_eq!(read_edges[0].source_node_index, 0);
+        assert_eq!(read_edges[0].target_node_index, 1);
+    }
+
+    #[test]
+    fn parallel_decode_matches_sequential_order() {
+        let nodes: Vec<Node> = (0..50)
+            .map(|i| node(i as i64, i * 1000, i * 2000))
+            .collect();
+
+        let mut buf = Vec::new();
+        {
+            let mut writer = OsrmWriter::new(&mut buf).unwrap();
+            writer.write_nodes(nodes.clone()).unwrap();
+            writer.finish().unwrap();
+        }
+
+        let mut reader = OsrmReader::new(Cursor::new(buf));
+        let mut decoded = Vec::new();
+
+        for entry in reader.entries().unwrap() {
+            if let Entry::Nodes(it) = entry.unwrap() {
+                decoded = it.read_all_nodes_parallel().unwrap();
+            }
+        }
+
+        assert_eq!(decoded.len(), nodes.len());
+        for (decoded, original) in decoded.iter().zip(nodes.iter()) {
+            assert_eq!(decoded.node_id, original.node_id);
+            assert_eq!(decoded.raw_latitude, original.raw_latitude);
+            assert_eq!(decoded.raw_longitude, original.raw_longitude);
+        }
+    }
+
+    #[test]
+    fn parses_barriers_traffic_lights_and_restrictions() {
+        let mut barrier_bytes = Vec::new();
+        barrier_bytes.extend_from_slice(&3u32.to_le_bytes());
+        barrier_bytes.extend_from_slice(&7u32.to_le_bytes());
+
+        let mut traffic_light_bytes = Vec::new();
+        traffic_light_bytes.extend_from_slice(&1u32.to_le_bytes());
+
+        let mut restriction_bytes = Vec::new();
+        restriction_bytes.extend_from_slice(&2u32.to_le_bytes());
+        restriction_bytes.extend_from_slice(&5u32.to_le_bytes());
+        restriction_bytes.extend_from_slice(&9u32.to_le_bytes());
+
+        let archive = build_archive(&[
+            ("/extractor/barriers", &barrier_bytes),
+            ("/extractor/traffic_lights", &traffic_light_bytes),
+            ("/extractor/restrictions", &restriction_bytes),
+        ]);
+
+        let mut reader = OsrmReader::new(Cursor::new(archive));
+        let mut barriers = Vec::new();
+        let mut traffic_lights = Vec::new();
+        let mut restrictions = Vec::new();
+
+        for entry in reader.entries().unwrap() {
+            match entry.unwrap() {
+                Entry::Barriers(it) => {
+                    for b in it {
+                        barriers.push(b.unwrap());
+                    }
+                },
+                Entry::TrafficLights(it) => {
+                    for t in it {
+                        traffic_lights.push(t.unwrap());
+                    }
+                },
+                Entry::Restrictions(it) => {
+                    for r in it {
+                        restrictions.push(r.unwrap());
+                    }
+                },
+                _ => {},
+            }
+        }
+
+        assert_eq!(barriers, vec![3, 7]);
+        assert_eq!(traffic_lights, vec![1]);
+        assert_eq!(restrictions.len(), 1);
+        assert_eq!(restrictions[0].from_edge_index, 2);
+        assert_eq!(restrictions[0].via_node_index, 5);
+        assert_eq!(restrictions[0].to_edge_index, 9);
+    }
+
+    #[test]
+    fn bbox_filter_builds_a_node_filter_for_edges() {
+        let nodes = vec![
+            node(1, 52_000_000, 13_000_000), // inside the box
+            node(2, 52_100_000, 13_100_000), // inside the box
+            node(3, 60_000_000, 20_000_000), // outside the box
+        ];
+        let edges = vec![
+            Edge { source_node_index: 0, target_node_index: 1 },
+            Edge { source_node_index: 1, target_node_index: 2 },
+        ];
+
+        let mut buf = Vec::new();
+        {
+            let mut writer = OsrmWriter::new(&mut buf).unwrap();
+            writer.write_nodes(nodes.clone()).unwrap();
+            writer.write_edges(edges.clone()).unwrap();
+            writer.finish().unwrap();
+        }
+
+        let mut reader = OsrmReader::new(Cursor::new(buf));
+        let mut node_filter = None;
+        let mut filtered_edges = Vec::new();
+
+        for entry in reader.entries().unwrap() {
+            match entry.unwrap() {
+                Entry::Nodes(it) => {
+                    node_filter = Some(it.within_bbox(51.0, 12.0, 53.0, 14.0).node_filter().unwrap());
+                },
+                Entry::Edges(it) => {
+                    let filter = node_filter.take().unwrap();
+                    for e in it.filtered(filter) {
+                        filtered_edges.push(e.unwrap());
+                    }
+                },
+                _ => {},
+            }
+        }
+
+        assert_eq!(filtered_edges.len(), 1);
+        assert_eq!(filtered_edges[0].source_node_index, 0);
+        assert_eq!(filtered_edges[0].target_node_index, 1);
+    }
+}